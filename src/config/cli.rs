@@ -0,0 +1,193 @@
+use super::{env as env_overlay, Config, ConfigLoader, ConfigSource};
+use anyhow::Result;
+use clap::Parser;
+use std::path::PathBuf;
+use tracing::level_filters::LevelFilter;
+
+/// Command-line flags, fed into the config merge as the highest-precedence `CommandArg` layer.
+#[derive(Debug, Parser)]
+#[command(name = "lazyagent", version, about)]
+pub struct Opts {
+    /// Overrides `Config::default_path()` / the discovered user config.
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Increase log verbosity; repeatable (-v info, -vv debug, -vvv trace).
+    #[arg(short, long, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Decrease log verbosity; repeatable, silences logging entirely.
+    #[arg(short, long, action = clap::ArgAction::Count, conflicts_with = "verbose")]
+    pub quiet: u8,
+
+    /// Overrides `agent.max_iterations` for every project.
+    #[arg(long)]
+    pub max_iterations: Option<u32>,
+
+    /// Overrides `agent.auto_pr = false` for every project.
+    #[arg(long)]
+    pub no_auto_pr: bool,
+
+    /// Overrides `agent.engine`.
+    #[arg(long)]
+    pub engine: Option<String>,
+}
+
+impl Opts {
+    /// The `tracing` level these flags resolve to.
+    pub fn tracing_level(&self) -> LevelFilter {
+        if self.quiet > 0 {
+            return LevelFilter::OFF;
+        }
+        match self.verbose {
+            0 => LevelFilter::ERROR,
+            1 => LevelFilter::INFO,
+            2 => LevelFilter::DEBUG,
+            _ => LevelFilter::TRACE,
+        }
+    }
+
+    /// Initializes the global `tracing` subscriber at this level.
+    pub fn init_tracing(&self) {
+        tracing_subscriber::fmt()
+            .with_max_level(self.tracing_level())
+            .init();
+    }
+
+    /// This flag set's contribution to the config merge, as a `CommandArg`
+    /// overlay. Also targets every project's `overrides` in `project_names`,
+    /// so `--max-iterations`/`--no-auto-pr` win over `[projects.overrides]` too.
+    pub(crate) fn as_config_overlay(&self, project_names: &[String]) -> toml::Value {
+        let mut agent = toml::value::Table::new();
+        if let Some(max_iterations) = self.max_iterations {
+            agent.insert(
+                "max_iterations".to_string(),
+                toml::Value::Integer(max_iterations as i64),
+            );
+        }
+        if self.no_auto_pr {
+            agent.insert("auto_pr".to_string(), toml::Value::Boolean(false));
+        }
+        if let Some(engine) = &self.engine {
+            agent.insert("engine".to_string(), toml::Value::String(engine.clone()));
+        }
+
+        let mut overrides = toml::value::Table::new();
+        if let Some(max_iterations) = self.max_iterations {
+            overrides.insert(
+                "max_iterations".to_string(),
+                toml::Value::Integer(max_iterations as i64),
+            );
+        }
+        if self.no_auto_pr {
+            overrides.insert("auto_pr".to_string(), toml::Value::Boolean(false));
+        }
+
+        let mut root = toml::value::Table::new();
+        if !agent.is_empty() {
+            root.insert("agent".to_string(), toml::Value::Table(agent));
+        }
+        if !overrides.is_empty() && !project_names.is_empty() {
+            let projects: Vec<toml::Value> = project_names
+                .iter()
+                .map(|name| {
+                    let mut project = toml::value::Table::new();
+                    project.insert("name".to_string(), toml::Value::String(name.clone()));
+                    project.insert(
+                        "overrides".to_string(),
+                        toml::Value::Table(overrides.clone()),
+                    );
+                    toml::Value::Table(project)
+                })
+                .collect();
+            root.insert("projects".to_string(), toml::Value::Array(projects));
+        }
+        toml::Value::Table(root)
+    }
+
+    /// Runs the full layered load (default -> user -> repo -> env -> CLI).
+    pub fn load_config(&self) -> Result<Config> {
+        let mut loader = ConfigLoader::new()?;
+
+        let user_path = match &self.config {
+            Some(path) => Some(path.clone()),
+            None => ConfigLoader::resolve_user_path()?,
+        };
+        if let Some(path) = &user_path {
+            loader.apply_file(path, ConfigSource::User)?;
+        }
+
+        loader.discover_and_apply_repo_configs()?;
+        loader.apply_value(env_overlay::load_overlay()?, ConfigSource::Env);
+        let project_names = loader.project_names()?;
+        loader.apply_value(self.as_config_overlay(&project_names), ConfigSource::CommandArg);
+
+        let (config, _provenance) = loader.finish()?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracing_level_default_is_error() {
+        let opts = Opts::parse_from(["lazyagent"]);
+        assert_eq!(opts.tracing_level(), LevelFilter::ERROR);
+    }
+
+    #[test]
+    fn test_tracing_level_verbose_steps() {
+        assert_eq!(Opts::parse_from(["lazyagent", "-v"]).tracing_level(), LevelFilter::INFO);
+        assert_eq!(Opts::parse_from(["lazyagent", "-vv"]).tracing_level(), LevelFilter::DEBUG);
+        assert_eq!(Opts::parse_from(["lazyagent", "-vvv"]).tracing_level(), LevelFilter::TRACE);
+        assert_eq!(Opts::parse_from(["lazyagent", "-vvvv"]).tracing_level(), LevelFilter::TRACE);
+    }
+
+    #[test]
+    fn test_tracing_level_quiet_overrides_verbose() {
+        let opts = Opts::parse_from(["lazyagent", "-q"]);
+        assert_eq!(opts.tracing_level(), LevelFilter::OFF);
+
+        // clap rejects -v together with -q, so build the combination directly.
+        let opts = Opts {
+            config: None,
+            verbose: 2,
+            quiet: 1,
+            max_iterations: None,
+            no_auto_pr: false,
+            engine: None,
+        };
+        assert_eq!(opts.tracing_level(), LevelFilter::OFF);
+    }
+
+    #[test]
+    fn test_as_config_overlay_empty_when_no_flags_set() {
+        let opts = Opts::parse_from(["lazyagent"]);
+        let overlay = opts.as_config_overlay(&["demo".to_string()]);
+        assert_eq!(overlay, toml::Value::Table(toml::value::Table::new()));
+    }
+
+    #[test]
+    fn test_as_config_overlay_sets_top_level_agent_fields() {
+        let opts = Opts::parse_from(["lazyagent", "--max-iterations", "1", "--no-auto-pr"]);
+        let overlay = opts.as_config_overlay(&[]);
+        let agent = overlay.get("agent").unwrap();
+        assert_eq!(agent.get("max_iterations").unwrap().as_integer(), Some(1));
+        assert_eq!(agent.get("auto_pr").unwrap().as_bool(), Some(false));
+    }
+
+    #[test]
+    fn test_as_config_overlay_also_targets_every_project_override() {
+        let opts = Opts::parse_from(["lazyagent", "--max-iterations", "1"]);
+        let overlay = opts.as_config_overlay(&["a".to_string(), "b".to_string()]);
+        let projects = overlay.get("projects").unwrap().as_array().unwrap();
+        assert_eq!(projects.len(), 2);
+        for (project, name) in projects.iter().zip(["a", "b"]) {
+            assert_eq!(project.get("name").unwrap().as_str(), Some(name));
+            let overrides = project.get("overrides").unwrap();
+            assert_eq!(overrides.get("max_iterations").unwrap().as_integer(), Some(1));
+        }
+    }
+}
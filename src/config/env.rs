@@ -0,0 +1,140 @@
+use anyhow::{Context, Result};
+use std::env;
+
+/// Reads the documented `LAZYAGENT_*` variables into an `Env` overlay.
+pub fn load_overlay() -> Result<toml::Value> {
+    let mut agent = toml::value::Table::new();
+    let mut ui = toml::value::Table::new();
+
+    if let Some(value) = read_var("LAZYAGENT_AGENT_MAX_ITERATIONS")? {
+        agent.insert(
+            "max_iterations".to_string(),
+            toml::Value::Integer(parse_int::<u32>("LAZYAGENT_AGENT_MAX_ITERATIONS", &value)? as i64),
+        );
+    }
+    if let Some(value) = read_var("LAZYAGENT_AGENT_AUTO_PR")? {
+        agent.insert(
+            "auto_pr".to_string(),
+            toml::Value::Boolean(parse_bool("LAZYAGENT_AGENT_AUTO_PR", &value)?),
+        );
+    }
+    if let Some(value) = read_var("LAZYAGENT_AGENT_ENGINE")? {
+        agent.insert("engine".to_string(), toml::Value::String(value));
+    }
+    if let Some(value) = read_var("LAZYAGENT_UI_REFRESH_MS")? {
+        ui.insert(
+            "refresh_ms".to_string(),
+            toml::Value::Integer(parse_int::<u64>("LAZYAGENT_UI_REFRESH_MS", &value)? as i64),
+        );
+    }
+
+    let mut root = toml::value::Table::new();
+    if !agent.is_empty() {
+        root.insert("agent".to_string(), toml::Value::Table(agent));
+    }
+    if !ui.is_empty() {
+        root.insert("ui".to_string(), toml::Value::Table(ui));
+    }
+    Ok(toml::Value::Table(root))
+}
+
+fn read_var(name: &str) -> Result<Option<String>> {
+    match env::var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(env::VarError::NotUnicode(_)) => {
+            anyhow::bail!("Environment variable {name} is not valid UTF-8")
+        }
+    }
+}
+
+fn parse_bool(name: &str, value: &str) -> Result<bool> {
+    value.parse::<bool>().with_context(|| {
+        format!("Environment variable {name} must be 'true' or 'false', got '{value}'")
+    })
+}
+
+fn parse_int<T: std::str::FromStr>(name: &str, value: &str) -> Result<T> {
+    value
+        .parse::<T>()
+        .map_err(|_| anyhow::anyhow!("Environment variable {name} must be a non-negative integer, got '{value}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // `load_overlay` reads real process env vars, so tests that set them
+    // must not run concurrently with each other.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    const VARS: &[&str] = &[
+        "LAZYAGENT_AGENT_MAX_ITERATIONS",
+        "LAZYAGENT_AGENT_AUTO_PR",
+        "LAZYAGENT_AGENT_ENGINE",
+        "LAZYAGENT_UI_REFRESH_MS",
+    ];
+
+    fn clear_vars() {
+        for var in VARS {
+            env::remove_var(var);
+        }
+    }
+
+    #[test]
+    fn test_load_overlay_empty_when_unset() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_vars();
+        let overlay = load_overlay().unwrap();
+        assert_eq!(overlay, toml::Value::Table(toml::value::Table::new()));
+    }
+
+    #[test]
+    fn test_load_overlay_parses_set_vars() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_vars();
+        env::set_var("LAZYAGENT_AGENT_MAX_ITERATIONS", "7");
+        env::set_var("LAZYAGENT_AGENT_AUTO_PR", "false");
+        env::set_var("LAZYAGENT_AGENT_ENGINE", "claude");
+        env::set_var("LAZYAGENT_UI_REFRESH_MS", "150");
+
+        let overlay = load_overlay().unwrap();
+        let agent = overlay.get("agent").unwrap();
+        assert_eq!(agent.get("max_iterations").unwrap().as_integer(), Some(7));
+        assert_eq!(agent.get("auto_pr").unwrap().as_bool(), Some(false));
+        assert_eq!(agent.get("engine").unwrap().as_str(), Some("claude"));
+        assert_eq!(
+            overlay.get("ui").unwrap().get("refresh_ms").unwrap().as_integer(),
+            Some(150)
+        );
+
+        clear_vars();
+    }
+
+    #[test]
+    fn test_load_overlay_rejects_invalid_int() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_vars();
+        env::set_var("LAZYAGENT_AGENT_MAX_ITERATIONS", "not-a-number");
+
+        let err = load_overlay().unwrap_err();
+        assert!(err.to_string().contains("LAZYAGENT_AGENT_MAX_ITERATIONS"));
+        assert!(err.to_string().contains("must be a non-negative integer"));
+
+        clear_vars();
+    }
+
+    #[test]
+    fn test_load_overlay_rejects_invalid_bool() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        clear_vars();
+        env::set_var("LAZYAGENT_AGENT_AUTO_PR", "yes");
+
+        let err = load_overlay().unwrap_err();
+        assert!(err.to_string().contains("LAZYAGENT_AGENT_AUTO_PR"));
+        assert!(err.to_string().contains("must be 'true' or 'false'"));
+
+        clear_vars();
+    }
+}
@@ -1,7 +1,13 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+mod cli;
+mod env;
+mod watch;
+pub use cli::Opts;
+pub use watch::{spawn_hot_reload, LiveConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -174,6 +180,236 @@ impl Default for Config {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ConfigSource {
+    Default,
+    User,
+    Repo,
+    Env,
+    CommandArg,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnnotatedValue {
+    pub path: Vec<String>,
+    pub source: ConfigSource,
+    pub value: toml::Value,
+}
+
+#[derive(Debug)]
+pub enum ConfigLoadError {
+    AmbiguousSource { candidates: Vec<PathBuf> },
+}
+
+impl std::fmt::Display for ConfigLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigLoadError::AmbiguousSource { candidates } => write!(
+                f,
+                "ambiguous config location: found {} candidates ({}), keep only one",
+                candidates.len(),
+                candidates
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigLoadError {}
+
+pub struct ConfigLoader {
+    merged: toml::Value,
+    provenance: Vec<AnnotatedValue>,
+}
+
+impl ConfigLoader {
+    pub fn new() -> Result<Self> {
+        let default_value = toml::Value::try_from(Config::default())
+            .context("Failed to serialize default config")?;
+        let mut loader = Self {
+            merged: toml::Value::Table(toml::value::Table::new()),
+            provenance: Vec::new(),
+        };
+        loader.apply_value(default_value, ConfigSource::Default);
+        Ok(loader)
+    }
+
+    fn user_config_candidates() -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
+        if let Ok(xdg_path) = Config::default_path() {
+            candidates.push(xdg_path);
+        }
+        if let Some(home) = dirs::home_dir() {
+            candidates.push(home.join(".lazyagent.toml"));
+        }
+        candidates
+    }
+
+    pub fn resolve_user_path() -> Result<Option<PathBuf>> {
+        Self::resolve_from_candidates(Self::user_config_candidates())
+    }
+
+    fn resolve_from_candidates(candidates: Vec<PathBuf>) -> Result<Option<PathBuf>> {
+        let existing: Vec<PathBuf> = candidates.into_iter().filter(|p| p.is_file()).collect();
+
+        match existing.len() {
+            0 => Ok(None),
+            1 => Ok(existing.into_iter().next()),
+            _ => Err(ConfigLoadError::AmbiguousSource { candidates: existing }.into()),
+        }
+    }
+
+    pub fn find_repo_config(start: &Path) -> Option<PathBuf> {
+        let mut dir = start.to_path_buf();
+        loop {
+            let candidate = dir.join(".lazyagent.toml");
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+            if !dir.pop() {
+                return None;
+            }
+        }
+    }
+
+    pub fn apply_file(&mut self, path: &Path, source: ConfigSource) -> Result<()> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        let value: toml::Value = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        self.apply_value(value, source);
+        Ok(())
+    }
+
+    pub fn apply_value(&mut self, value: toml::Value, source: ConfigSource) {
+        let mut path = Vec::new();
+        merge_layer(&mut self.merged, &value, source, &mut path, &mut self.provenance);
+    }
+
+    pub fn discover_and_apply_repo_configs(&mut self) -> Result<()> {
+        let partial: Config = self
+            .merged
+            .clone()
+            .try_into()
+            .context("Failed to assemble config while discovering repo-level overrides")?;
+
+        for project in &partial.projects {
+            if let Some(repo_config_path) = Self::find_repo_config(&project.repo_path) {
+                self.apply_file(&repo_config_path, ConfigSource::Repo)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn project_names(&self) -> Result<Vec<String>> {
+        let partial: Config = self
+            .merged
+            .clone()
+            .try_into()
+            .context("Failed to assemble config while listing project names")?;
+        Ok(partial.projects.into_iter().map(|p| p.name).collect())
+    }
+
+    pub fn finish(self) -> Result<(Config, Vec<AnnotatedValue>)> {
+        let config: Config = self
+            .merged
+            .try_into()
+            .context("Failed to assemble merged config")?;
+        config
+            .validate()
+            .map_err(|e| anyhow::anyhow!("Config validation failed: {e}"))?;
+        Ok((config, self.provenance))
+    }
+}
+
+fn merge_layer(
+    base: &mut toml::Value,
+    overlay: &toml::Value,
+    source: ConfigSource,
+    path: &mut Vec<String>,
+    provenance: &mut Vec<AnnotatedValue>,
+) {
+    match overlay {
+        toml::Value::Table(overlay_table) => {
+            if !matches!(base, toml::Value::Table(_)) {
+                *base = toml::Value::Table(toml::value::Table::new());
+            }
+            let base_table = base.as_table_mut().expect("just coerced to a table");
+
+            for (key, overlay_value) in overlay_table {
+                path.push(key.clone());
+                if key == "projects" {
+                    merge_projects(base_table, overlay_value, source, path, provenance);
+                } else {
+                    let base_value = base_table
+                        .entry(key.clone())
+                        .or_insert_with(|| toml::Value::Table(toml::value::Table::new()));
+                    merge_layer(base_value, overlay_value, source, path, provenance);
+                }
+                path.pop();
+            }
+        }
+        leaf => {
+            *base = leaf.clone();
+            match provenance.iter_mut().find(|v| v.path == *path) {
+                Some(existing) => {
+                    existing.source = source;
+                    existing.value = leaf.clone();
+                }
+                None => provenance.push(AnnotatedValue {
+                    path: path.clone(),
+                    source,
+                    value: leaf.clone(),
+                }),
+            }
+        }
+    }
+}
+
+fn merge_projects(
+    base_table: &mut toml::value::Table,
+    overlay_projects: &toml::Value,
+    source: ConfigSource,
+    path: &mut Vec<String>,
+    provenance: &mut Vec<AnnotatedValue>,
+) {
+    let Some(overlay_array) = overlay_projects.as_array() else {
+        return;
+    };
+
+    let mut base_array = base_table
+        .get("projects")
+        .and_then(|v| v.as_array().cloned())
+        .unwrap_or_default();
+
+    for overlay_project in overlay_array {
+        let name = overlay_project.get("name").and_then(|v| v.as_str());
+        let existing_idx = name.and_then(|n| {
+            base_array
+                .iter()
+                .position(|p| p.get("name").and_then(|v| v.as_str()) == Some(n))
+        });
+
+        let idx = match existing_idx {
+            Some(idx) => idx,
+            None => {
+                base_array.push(toml::Value::Table(toml::value::Table::new()));
+                base_array.len() - 1
+            }
+        };
+
+        path.push(name.unwrap_or("<unnamed>").to_string());
+        merge_layer(&mut base_array[idx], overlay_project, source, path, provenance);
+        path.pop();
+    }
+
+    base_table.insert("projects".to_string(), toml::Value::Array(base_array));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -564,4 +800,182 @@ draft_pr = true
 
         fs::remove_file(&config_path).unwrap();
     }
+
+    #[test]
+    fn test_loader_defaults_only() {
+        let loader = ConfigLoader::new().unwrap();
+        let err = loader.finish().unwrap_err();
+        assert!(err.to_string().contains("At least one project must be configured"));
+    }
+
+    #[test]
+    fn test_loader_applies_user_layer_on_top_of_defaults() {
+        let mut loader = ConfigLoader::new().unwrap();
+        let user_value: toml::Value = toml::from_str(
+            r#"
+[ui]
+refresh_ms = 500
+
+[[projects]]
+name = "demo"
+repo_path = "/abs/demo"
+tasks_yaml = "/abs/demo/tasks.yaml"
+base_branch = "main"
+max_parallel = 1
+"#,
+        )
+        .unwrap();
+        loader.apply_value(user_value, ConfigSource::User);
+
+        let (config, provenance) = loader.finish().unwrap();
+        assert_eq!(config.ui.refresh_ms, 500);
+        assert_eq!(config.agent.engine, "claude");
+        assert_eq!(config.projects.len(), 1);
+
+        let refresh_entry = provenance
+            .iter()
+            .find(|v| v.path == vec!["ui".to_string(), "refresh_ms".to_string()])
+            .unwrap();
+        assert_eq!(refresh_entry.source, ConfigSource::User);
+    }
+
+    #[test]
+    fn test_loader_merges_projects_array_by_name() {
+        let mut loader = ConfigLoader::new().unwrap();
+        let user_value: toml::Value = toml::from_str(
+            r#"
+[[projects]]
+name = "demo"
+repo_path = "/abs/demo"
+tasks_yaml = "/abs/demo/tasks.yaml"
+base_branch = "main"
+max_parallel = 1
+"#,
+        )
+        .unwrap();
+        loader.apply_value(user_value, ConfigSource::User);
+
+        let repo_value: toml::Value = toml::from_str(
+            r#"
+[[projects]]
+name = "demo"
+max_parallel = 4
+"#,
+        )
+        .unwrap();
+        loader.apply_value(repo_value, ConfigSource::Repo);
+
+        let (config, _) = loader.finish().unwrap();
+        assert_eq!(config.projects.len(), 1);
+        assert_eq!(config.projects[0].name, "demo");
+        assert_eq!(config.projects[0].max_parallel, 4);
+        assert_eq!(
+            config.projects[0].repo_path,
+            PathBuf::from("/abs/demo"),
+            "unrelated fields from the lower layer must survive the merge"
+        );
+    }
+
+    #[test]
+    fn test_loader_appends_unmatched_project_entries() {
+        let mut loader = ConfigLoader::new().unwrap();
+        let user_value: toml::Value = toml::from_str(
+            r#"
+[[projects]]
+name = "demo"
+repo_path = "/abs/demo"
+tasks_yaml = "/abs/demo/tasks.yaml"
+base_branch = "main"
+max_parallel = 1
+"#,
+        )
+        .unwrap();
+        loader.apply_value(user_value, ConfigSource::User);
+
+        let repo_value: toml::Value = toml::from_str(
+            r#"
+[[projects]]
+name = "other"
+repo_path = "/abs/other"
+tasks_yaml = "/abs/other/tasks.yaml"
+base_branch = "main"
+max_parallel = 1
+"#,
+        )
+        .unwrap();
+        loader.apply_value(repo_value, ConfigSource::Repo);
+
+        let (config, _) = loader.finish().unwrap();
+        assert_eq!(config.projects.len(), 2);
+    }
+
+    #[test]
+    fn test_resolve_from_candidates_none_when_nothing_exists() {
+        let temp_dir = std::env::temp_dir();
+        let candidates = vec![
+            temp_dir.join(format!("lazyagent_missing_a_{}.toml", std::process::id())),
+            temp_dir.join(format!("lazyagent_missing_b_{}.toml", std::process::id())),
+        ];
+        assert!(ConfigLoader::resolve_from_candidates(candidates).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_resolve_from_candidates_picks_the_only_existing_one() {
+        let path = std::env::temp_dir().join(format!("lazyagent_single_candidate_{}.toml", std::process::id()));
+        fs::write(&path, "").unwrap();
+
+        let candidates = vec![
+            path.clone(),
+            std::env::temp_dir().join(format!("lazyagent_missing_{}.toml", std::process::id())),
+        ];
+        assert_eq!(ConfigLoader::resolve_from_candidates(candidates).unwrap(), Some(path.clone()));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_from_candidates_errors_when_more_than_one_exists() {
+        let temp_dir = std::env::temp_dir();
+        let xdg_path = temp_dir.join(format!("lazyagent_ambiguous_xdg_{}.toml", std::process::id()));
+        let legacy_path = temp_dir.join(format!("lazyagent_ambiguous_legacy_{}.toml", std::process::id()));
+        fs::write(&xdg_path, "").unwrap();
+        fs::write(&legacy_path, "").unwrap();
+
+        let err = ConfigLoader::resolve_from_candidates(vec![xdg_path.clone(), legacy_path.clone()]).unwrap_err();
+        assert!(err.to_string().contains("ambiguous config location"));
+        assert!(err.to_string().contains(&xdg_path.display().to_string()));
+        assert!(err.to_string().contains(&legacy_path.display().to_string()));
+
+        fs::remove_file(&xdg_path).unwrap();
+        fs::remove_file(&legacy_path).unwrap();
+    }
+
+    #[test]
+    fn test_find_repo_config_walks_up_from_nested_dir() {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "lazyagent_repo_config_test_{}",
+            std::process::id()
+        ));
+        let nested = temp_dir.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(temp_dir.join(".lazyagent.toml"), "").unwrap();
+
+        let found = ConfigLoader::find_repo_config(&nested);
+        assert_eq!(found, Some(temp_dir.join(".lazyagent.toml")));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_repo_config_none_when_absent() {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "lazyagent_repo_config_missing_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        assert!(ConfigLoader::find_repo_config(&temp_dir).is_none());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
 }
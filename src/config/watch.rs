@@ -0,0 +1,191 @@
+use super::{env as env_overlay, Config, ConfigLoader, ConfigSource};
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait for further change events before reloading.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A hot-reloadable view of the merged config.
+pub struct LiveConfig {
+    current: Arc<ArcSwap<Config>>,
+    last_error: Arc<ArcSwap<Option<String>>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl LiveConfig {
+    pub fn load(&self) -> Arc<Config> {
+        self.current.load_full()
+    }
+
+    /// The error from the most recent failed reload, if any.
+    pub fn last_error(&self) -> Option<String> {
+        (*self.last_error.load_full()).clone()
+    }
+}
+
+/// Loads `initial` into a `LiveConfig` and spawns a watcher on `user_path`
+/// plus every path in `repo_paths`. On a debounced change, reruns the full
+/// load+validate pipeline (files, env overlay, then `command_overlay`) and
+/// swaps in the result, or records the error via `LiveConfig::last_error`.
+pub fn spawn_hot_reload(
+    initial: Config,
+    user_path: PathBuf,
+    repo_paths: Vec<PathBuf>,
+    command_overlay: toml::Value,
+) -> Result<LiveConfig> {
+    let current = Arc::new(ArcSwap::from_pointee(initial));
+    let last_error = Arc::new(ArcSwap::from_pointee(None));
+
+    let (tx, rx) = channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).context("Failed to create config file watcher")?;
+
+    let mut watched_dirs = HashSet::new();
+    for path in std::iter::once(&user_path).chain(repo_paths.iter()) {
+        if let Some(parent) = path.parent() {
+            if watched_dirs.insert(parent.to_path_buf()) {
+                watcher
+                    .watch(parent, RecursiveMode::NonRecursive)
+                    .with_context(|| format!("Failed to watch {}", parent.display()))?;
+            }
+        }
+    }
+
+    let swap = Arc::clone(&current);
+    let error_slot = Arc::clone(&last_error);
+    thread::spawn(move || {
+        while let Ok(first) = rx.recv() {
+            if first.is_err() {
+                continue;
+            }
+            // Drain any further events within the debounce window.
+            while let Ok(Ok(_)) = rx.recv_timeout(DEBOUNCE) {}
+
+            match reload_from_disk(&user_path, &repo_paths, &command_overlay) {
+                Ok(new_config) => {
+                    swap.store(Arc::new(new_config));
+                    error_slot.store(Arc::new(None));
+                }
+                Err(e) => error_slot.store(Arc::new(Some(format!("{e:#}")))),
+            }
+        }
+    });
+
+    Ok(LiveConfig {
+        current,
+        last_error,
+        _watcher: watcher,
+    })
+}
+
+fn reload_from_disk(
+    user_path: &Path,
+    repo_paths: &[PathBuf],
+    command_overlay: &toml::Value,
+) -> Result<Config> {
+    let mut loader = ConfigLoader::new()?;
+    if user_path.is_file() {
+        loader.apply_file(user_path, ConfigSource::User)?;
+    }
+    for repo_path in repo_paths {
+        if repo_path.is_file() {
+            loader.apply_file(repo_path, ConfigSource::Repo)?;
+        }
+    }
+    loader.apply_value(env_overlay::load_overlay()?, ConfigSource::Env);
+    loader.apply_value(command_overlay.clone(), ConfigSource::CommandArg);
+    let (config, _provenance) = loader.finish()?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Instant;
+
+    const PROJECT_TOML: &str = r#"
+[[projects]]
+name = "demo"
+repo_path = "/abs/demo"
+tasks_yaml = "/abs/demo/tasks.yaml"
+base_branch = "main"
+max_parallel = 1
+"#;
+
+    fn temp_config_path(label: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "lazyagent_watch_test_{label}_{}_{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ))
+    }
+
+    /// Polls `f` until it returns `Some`, or panics after the debounce
+    /// window has had plenty of time to fire.
+    fn wait_for<T>(mut f: impl FnMut() -> Option<T>) -> T {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            if let Some(value) = f() {
+                return value;
+            }
+            if Instant::now() > deadline {
+                panic!("timed out waiting for hot reload");
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    fn test_spawn_hot_reload_picks_up_valid_change() {
+        let path = temp_config_path("valid");
+        fs::write(&path, format!("[ui]\nrefresh_ms = 200\n{PROJECT_TOML}")).unwrap();
+
+        let initial = reload_from_disk(&path, &[], &toml::Value::Table(toml::value::Table::new()))
+            .unwrap();
+        let live = spawn_hot_reload(
+            initial,
+            path.clone(),
+            Vec::new(),
+            toml::Value::Table(toml::value::Table::new()),
+        )
+        .unwrap();
+        assert_eq!(live.load().ui.refresh_ms, 200);
+
+        fs::write(&path, format!("[ui]\nrefresh_ms = 999\n{PROJECT_TOML}")).unwrap();
+        wait_for(|| (live.load().ui.refresh_ms == 999).then_some(()));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_spawn_hot_reload_keeps_old_config_on_invalid_change() {
+        let path = temp_config_path("invalid");
+        fs::write(&path, format!("[ui]\nrefresh_ms = 200\n{PROJECT_TOML}")).unwrap();
+
+        let initial = reload_from_disk(&path, &[], &toml::Value::Table(toml::value::Table::new()))
+            .unwrap();
+        let live = spawn_hot_reload(
+            initial,
+            path.clone(),
+            Vec::new(),
+            toml::Value::Table(toml::value::Table::new()),
+        )
+        .unwrap();
+
+        fs::write(&path, "not valid toml [[[").unwrap();
+        wait_for(|| live.last_error());
+
+        assert_eq!(live.load().ui.refresh_ms, 200, "old config must survive a bad reload");
+        assert!(live.last_error().unwrap().contains("Failed to parse config file"));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,8 +1,18 @@
-use anyhow::{anyhow, bail, Context, Result};
+use anyhow::{bail, Context, Result};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Task {
@@ -11,11 +21,158 @@ pub struct Task {
     pub completed: bool,
     #[serde(default)]
     pub depends: Vec<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub created: Option<NaiveDateTime>,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    #[serde(default)]
+    pub args: HashMap<String, String>,
+    #[serde(default)]
+    pub due: Option<NaiveDate>,
+    #[serde(default)]
+    pub estimate: Option<Duration>,
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+    #[serde(default)]
+    pub inputs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimeEntry {
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub message: Option<String>,
+    pub minutes: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn fnv1a_hash(data: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    data.iter()
+        .fold(OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+fn compute_fingerprint(task: &Task, dep_fingerprints: &[&str]) -> String {
+    let mut input_paths: Vec<PathBuf> = Vec::new();
+    for pattern in &task.inputs {
+        if let Ok(matches) = glob::glob(pattern) {
+            input_paths.extend(matches.filter_map(|m| m.ok()));
+        }
+    }
+    input_paths.sort();
+
+    let mut combined = String::new();
+    for path in &input_paths {
+        if let Ok(bytes) = fs::read(path) {
+            combined.push_str(&format!("{:016x}", fnv1a_hash(&bytes)));
+        }
+    }
+    for dep_fingerprint in dep_fingerprints {
+        combined.push_str(dep_fingerprint);
+    }
+
+    format!("{:016x}", fnv1a_hash(combined.as_bytes()))
+}
+
+fn render_template(input: &str, scope: &HashMap<String, String>) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            bail!("Unterminated template placeholder in '{input}'");
+        };
+
+        let key = after_open[..end].trim();
+        let value = scope
+            .get(key)
+            .with_context(|| format!("Undefined template variable '{{{{{key}}}}}'"))?;
+        output.push_str(value);
+
+        rest = &after_open[end + 2..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+impl Default for Task {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            title: String::new(),
+            completed: false,
+            depends: Vec::new(),
+            priority: Priority::default(),
+            tags: Vec::new(),
+            created: None,
+            weight: default_weight(),
+            args: HashMap::new(),
+            due: None,
+            estimate: None,
+            time_entries: Vec::new(),
+            fingerprint: None,
+            inputs: Vec::new(),
+        }
+    }
+}
+
+impl Task {
+    pub fn urgency(&self, file: &TasksFile) -> f64 {
+        let blockers = file
+            .tasks
+            .iter()
+            .filter(|t| t.depends.contains(&self.id))
+            .count();
+        self.urgency_with_blockers(blockers)
+    }
+
+    fn urgency_with_blockers(&self, blockers: usize) -> f64 {
+        let mut score = match self.priority {
+            Priority::High => 6.0,
+            Priority::Medium => 3.9,
+            Priority::Low => 1.8,
+        };
+
+        score += 8.0 * (blockers.min(3) as f64 / 3.0);
+
+        score += (self.tags.len() as f64).min(3.0);
+
+        if let Some(created) = self.created {
+            let age_days = (chrono::Utc::now().naive_utc() - created).num_days().max(0) as f64;
+            score += (age_days * 0.01).min(2.0);
+        }
+
+        if let Some(due) = self.due {
+            let days_until = (due - chrono::Utc::now().date_naive()).num_days();
+            score += if days_until <= 0 {
+                12.0
+            } else {
+                12.0 / (days_until as f64 + 1.0)
+            };
+        }
+
+        score
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TasksFile {
     pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub vars: HashMap<String, String>,
 }
 
 impl TasksFile {
@@ -27,18 +184,46 @@ impl TasksFile {
             )
         })?;
 
-        let tasks_file: TasksFile = serde_yaml::from_str(&content).with_context(|| {
+        let mut tasks_file: TasksFile = serde_yaml::from_str(&content).with_context(|| {
             format!(
                 "Failed to parse tasks YAML file: {}",
                 path.as_ref().display()
             )
         })?;
 
+        tasks_file.expand_templates()?;
         tasks_file.validate()?;
 
         Ok(tasks_file)
     }
 
+    fn expand_templates(&mut self) -> Result<()> {
+        let file_vars = self.vars.clone();
+
+        for task in &mut self.tasks {
+            let mut scope = file_vars.clone();
+            scope.extend(task.args.clone());
+
+            task.title = render_template(&task.title, &scope).with_context(|| {
+                format!("Task '{}' has an unresolved template variable in its title", task.id)
+            })?;
+
+            task.depends = task
+                .depends
+                .iter()
+                .map(|dep| render_template(dep, &scope))
+                .collect::<Result<Vec<_>>>()
+                .with_context(|| {
+                    format!(
+                        "Task '{}' has an unresolved template variable in its depends",
+                        task.id
+                    )
+                })?;
+        }
+
+        Ok(())
+    }
+
     pub fn total_tasks(&self) -> usize {
         self.tasks.len()
     }
@@ -93,46 +278,63 @@ impl TasksFile {
     }
 
     fn check_no_cycles(&self) -> Result<()> {
-        let mut in_degree: HashMap<&String, usize> = HashMap::new();
-        let mut adj_list: HashMap<&String, Vec<&String>> = HashMap::new();
-
-        for task in &self.tasks {
-            in_degree.insert(&task.id, task.depends.len());
-            adj_list.insert(&task.id, Vec::new());
+        if let Some(path) = self.find_cycle() {
+            return Err(CycleError { path }.into());
         }
+        Ok(())
+    }
+
+    fn find_cycle(&self) -> Option<Vec<String>> {
+        let mut color: HashMap<&str, Color> = self
+            .tasks
+            .iter()
+            .map(|t| (t.id.as_str(), Color::White))
+            .collect();
+        let mut stack: Vec<&str> = Vec::new();
 
         for task in &self.tasks {
-            for dep_id in &task.depends {
-                adj_list.get_mut(dep_id).unwrap().push(&task.id);
+            if color[task.id.as_str()] == Color::White {
+                if let Some(cycle) = self.visit(&task.id, &mut color, &mut stack) {
+                    return Some(cycle);
+                }
             }
         }
 
-        let mut queue: Vec<&String> = in_degree
-            .iter()
-            .filter(|(_, &degree)| degree == 0)
-            .map(|(id, _)| *id)
-            .collect();
+        None
+    }
 
-        let mut processed = 0;
+    fn visit<'a>(
+        &'a self,
+        id: &'a str,
+        color: &mut HashMap<&'a str, Color>,
+        stack: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        color.insert(id, Color::Gray);
+        stack.push(id);
 
-        while let Some(task_id) = queue.pop() {
-            processed += 1;
-            if let Some(dependents) = adj_list.get(task_id) {
-                for dependent_id in dependents {
-                    let degree = in_degree.get_mut(dependent_id).unwrap();
-                    *degree -= 1;
-                    if *degree == 0 {
-                        queue.push(dependent_id);
+        if let Some(task) = self.get_task_by_id(id) {
+            for dep_id in &task.depends {
+                match color.get(dep_id.as_str()) {
+                    Some(Color::Gray) => {
+                        let start = stack.iter().position(|&s| s == dep_id.as_str())?;
+                        let mut cycle: Vec<String> =
+                            stack[start..].iter().map(|s| s.to_string()).collect();
+                        cycle.push(dep_id.clone());
+                        return Some(cycle);
+                    }
+                    Some(Color::Black) => {}
+                    Some(Color::White) | None => {
+                        if let Some(cycle) = self.visit(dep_id, color, stack) {
+                            return Some(cycle);
+                        }
                     }
                 }
             }
         }
 
-        if processed != self.tasks.len() {
-            bail!("Circular dependency detected in tasks");
-        }
-
-        Ok(())
+        stack.pop();
+        color.insert(id, Color::Black);
+        None
     }
 
     pub fn get_task_by_id(&self, id: &str) -> Option<&Task> {
@@ -140,7 +342,8 @@ impl TasksFile {
     }
 
     pub fn get_ready_tasks(&self) -> Vec<&Task> {
-        self.tasks
+        let ready: Vec<&Task> = self
+            .tasks
             .iter()
             .filter(|task| {
                 !task.completed
@@ -149,9 +352,112 @@ impl TasksFile {
                         .iter()
                         .all(|dep_id| self.get_task_by_id(dep_id).map_or(false, |t| t.completed))
             })
+            .collect();
+
+        let blocker_counts = self.blocker_counts();
+        let mut scored: Vec<(&Task, f64)> = ready
+            .into_iter()
+            .map(|task| {
+                let blockers = blocker_counts.get(task.id.as_str()).copied().unwrap_or(0);
+                (task, task.urgency_with_blockers(blockers))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        scored.into_iter().map(|(task, _)| task).collect()
+    }
+
+    fn blocker_counts(&self) -> HashMap<&str, usize> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for task in &self.tasks {
+            for dep_id in &task.depends {
+                *counts.entry(dep_id.as_str()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    pub fn overdue_tasks(&self, today: NaiveDate) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|t| !t.completed && t.due.is_some_and(|due| due < today))
             .collect()
     }
 
+    pub fn total_logged(&self) -> Duration {
+        self.tasks
+            .iter()
+            .flat_map(|t| &t.time_entries)
+            .fold(Duration::zero(), |acc, entry| {
+                acc + Duration::minutes(entry.minutes as i64)
+            })
+    }
+
+    pub fn total_estimated(&self) -> Duration {
+        self.tasks
+            .iter()
+            .fold(Duration::zero(), |acc, t| acc + t.estimate.unwrap_or_else(Duration::zero))
+    }
+
+    pub fn tasks_by_due(&self) -> Vec<&Task> {
+        let mut tasks = self.incomplete_tasks();
+        tasks.sort_by_key(|t| (t.due.is_none(), t.due));
+        tasks
+    }
+
+    pub fn stale_tasks(&self) -> Result<Vec<&Task>> {
+        let order = self.topological_order()?;
+
+        let mut fresh: HashMap<&str, String> = HashMap::new();
+        for task in &order {
+            let dep_fingerprints: Vec<&str> = task
+                .depends
+                .iter()
+                .filter_map(|dep_id| fresh.get(dep_id.as_str()).map(|s| s.as_str()))
+                .collect();
+            fresh.insert(task.id.as_str(), compute_fingerprint(task, &dep_fingerprints));
+        }
+
+        Ok(self
+            .tasks
+            .iter()
+            .filter(|t| t.completed)
+            .filter(|t| {
+                let Some(recorded) = t.fingerprint.as_deref() else {
+                    return false;
+                };
+                fresh.get(t.id.as_str()).map(|fp| fp.as_str()) != Some(recorded)
+            })
+            .collect())
+    }
+
+    pub fn recompute_fingerprints(&mut self) {
+        let Ok(order) = self.topological_order() else {
+            return;
+        };
+        let order_ids: Vec<String> = order.into_iter().map(|t| t.id.clone()).collect();
+
+        let mut fresh: HashMap<String, String> = HashMap::new();
+        for id in &order_ids {
+            let task = self
+                .get_task_by_id(id)
+                .expect("id from topological_order always exists in self.tasks");
+            let dep_fingerprints: Vec<&str> = task
+                .depends
+                .iter()
+                .filter_map(|dep_id| fresh.get(dep_id).map(|s| s.as_str()))
+                .collect();
+            fresh.insert(id.clone(), compute_fingerprint(task, &dep_fingerprints));
+        }
+
+        for task in &mut self.tasks {
+            if let Some(fingerprint) = fresh.get(&task.id) {
+                task.fingerprint = Some(fingerprint.clone());
+            }
+        }
+    }
+
     pub fn get_blocked_tasks(&self) -> Vec<(&Task, Vec<String>)> {
         self.tasks
             .iter()
@@ -186,7 +492,14 @@ impl TasksFile {
 
         for task in &self.tasks {
             for dep_id in &task.depends {
-                adj_list.get_mut(dep_id).unwrap().push(&task.id);
+                let Some(dependents) = adj_list.get_mut(dep_id) else {
+                    bail!(
+                        "Task '{}' depends on non-existent task '{}'",
+                        task.id,
+                        dep_id
+                    );
+                };
+                dependents.push(&task.id);
             }
         }
 
@@ -214,18 +527,141 @@ impl TasksFile {
         }
 
         if result.len() != self.tasks.len() {
-            return Err(anyhow!("Circular dependency detected in tasks"));
+            let path = self.find_cycle().unwrap_or_default();
+            return Err(CycleError { path }.into());
         }
 
         Ok(result)
     }
+
+    pub fn critical_path(&self) -> Result<Vec<&Task>> {
+        let order = self.topological_order()?;
+
+        let mut adj_list: HashMap<&str, Vec<&str>> = HashMap::new();
+        for task in &self.tasks {
+            adj_list.entry(task.id.as_str()).or_default();
+        }
+        for task in &self.tasks {
+            for dep_id in &task.depends {
+                adj_list
+                    .entry(dep_id.as_str())
+                    .or_default()
+                    .push(task.id.as_str());
+            }
+        }
+
+        let mut dist: HashMap<&str, u32> = HashMap::new();
+        let mut pred: HashMap<&str, &str> = HashMap::new();
+        for task in &order {
+            dist.insert(task.id.as_str(), task.weight);
+        }
+
+        for task in &order {
+            let current_dist = dist[task.id.as_str()];
+            for &dependent_id in adj_list.get(task.id.as_str()).into_iter().flatten() {
+                let Some(dependent) = self.get_task_by_id(dependent_id) else {
+                    continue;
+                };
+                let candidate = current_dist + dependent.weight;
+                if candidate > dist[dependent_id] {
+                    dist.insert(dependent_id, candidate);
+                    pred.insert(dependent_id, task.id.as_str());
+                }
+            }
+        }
+
+        let Some((&end_id, _)) = dist.iter().max_by_key(|(_, &d)| d) else {
+            return Ok(Vec::new());
+        };
+
+        let mut chain = vec![end_id];
+        let mut current = end_id;
+        while let Some(&predecessor) = pred.get(current) {
+            chain.push(predecessor);
+            current = predecessor;
+        }
+        chain.reverse();
+
+        Ok(chain
+            .into_iter()
+            .filter_map(|id| self.get_task_by_id(id))
+            .collect())
+    }
+
+    pub fn execution_waves(&self) -> Result<Vec<Vec<&Task>>> {
+        let order = self.topological_order()?;
+
+        let mut level: HashMap<&str, i32> = HashMap::new();
+        for task in &self.tasks {
+            if task.completed {
+                level.insert(task.id.as_str(), -1);
+            }
+        }
+
+        for task in &order {
+            if task.completed {
+                continue;
+            }
+            let lvl = task
+                .depends
+                .iter()
+                .filter_map(|dep_id| level.get(dep_id.as_str()))
+                .filter(|&&l| l >= 0)
+                .max()
+                .map(|&l| l + 1)
+                .unwrap_or(0);
+            level.insert(task.id.as_str(), lvl);
+        }
+
+        let max_level = level.values().filter(|&&l| l >= 0).max().copied().unwrap_or(-1);
+
+        let mut waves: Vec<Vec<&Task>> = vec![Vec::new(); (max_level + 1).max(0) as usize];
+        for task in &self.tasks {
+            if task.completed {
+                continue;
+            }
+            waves[level[task.id.as_str()] as usize].push(task);
+        }
+
+        Ok(waves)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    White,
+    Gray,
+    Black,
 }
 
+#[derive(Debug, Clone)]
+pub struct CycleError {
+    pub path: Vec<String>,
+}
+
+impl std::fmt::Display for CycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Circular dependency detected: {}", self.path.join(" -> "))
+    }
+}
+
+impl std::error::Error for CycleError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::io::Write;
 
+    fn task(id: &str, title: &str, completed: bool, depends: Vec<&str>) -> Task {
+        Task {
+            id: id.to_string(),
+            title: title.to_string(),
+            completed,
+            depends: depends.into_iter().map(String::from).collect(),
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test_task_deserialization() {
         let yaml = r#"
@@ -319,25 +755,11 @@ tasks:
     fn test_total_tasks() {
         let tasks_file = TasksFile {
             tasks: vec![
-                Task {
-                    id: "task-1".to_string(),
-                    title: "Task 1".to_string(),
-                    completed: true,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-2".to_string(),
-                    title: "Task 2".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-3".to_string(),
-                    title: "Task 3".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
+                task("task-1", "Task 1", true, vec![]),
+                task("task-2", "Task 2", false, vec![]),
+                task("task-3", "Task 3", false, vec![]),
             ],
+            ..Default::default()
         };
 
         assert_eq!(tasks_file.total_tasks(), 3);
@@ -347,25 +769,11 @@ tasks:
     fn test_completed_tasks() {
         let tasks_file = TasksFile {
             tasks: vec![
-                Task {
-                    id: "task-1".to_string(),
-                    title: "Task 1".to_string(),
-                    completed: true,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-2".to_string(),
-                    title: "Task 2".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-3".to_string(),
-                    title: "Task 3".to_string(),
-                    completed: true,
-                    depends: vec![],
-                },
+                task("task-1", "Task 1", true, vec![]),
+                task("task-2", "Task 2", false, vec![]),
+                task("task-3", "Task 3", true, vec![]),
             ],
+            ..Default::default()
         };
 
         assert_eq!(tasks_file.completed_tasks(), 2);
@@ -375,25 +783,11 @@ tasks:
     fn test_remaining_tasks() {
         let tasks_file = TasksFile {
             tasks: vec![
-                Task {
-                    id: "task-1".to_string(),
-                    title: "Task 1".to_string(),
-                    completed: true,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-2".to_string(),
-                    title: "Task 2".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-3".to_string(),
-                    title: "Task 3".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
+                task("task-1", "Task 1", true, vec![]),
+                task("task-2", "Task 2", false, vec![]),
+                task("task-3", "Task 3", false, vec![]),
             ],
+            ..Default::default()
         };
 
         assert_eq!(tasks_file.remaining_tasks(), 2);
@@ -403,25 +797,11 @@ tasks:
     fn test_incomplete_tasks() {
         let tasks_file = TasksFile {
             tasks: vec![
-                Task {
-                    id: "task-1".to_string(),
-                    title: "Task 1".to_string(),
-                    completed: true,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-2".to_string(),
-                    title: "Task 2".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-3".to_string(),
-                    title: "Task 3".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
+                task("task-1", "Task 1", true, vec![]),
+                task("task-2", "Task 2", false, vec![]),
+                task("task-3", "Task 3", false, vec![]),
             ],
+            ..Default::default()
         };
 
         let incomplete = tasks_file.incomplete_tasks();
@@ -434,25 +814,11 @@ tasks:
     fn test_completed_task_list() {
         let tasks_file = TasksFile {
             tasks: vec![
-                Task {
-                    id: "task-1".to_string(),
-                    title: "Task 1".to_string(),
-                    completed: true,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-2".to_string(),
-                    title: "Task 2".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-3".to_string(),
-                    title: "Task 3".to_string(),
-                    completed: true,
-                    depends: vec![],
-                },
+                task("task-1", "Task 1", true, vec![]),
+                task("task-2", "Task 2", false, vec![]),
+                task("task-3", "Task 3", true, vec![]),
             ],
+            ..Default::default()
         };
 
         let completed = tasks_file.completed_task_list();
@@ -531,19 +897,10 @@ tasks:
     fn test_get_task_by_id() {
         let tasks_file = TasksFile {
             tasks: vec![
-                Task {
-                    id: "task-1".to_string(),
-                    title: "Task 1".to_string(),
-                    completed: true,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-2".to_string(),
-                    title: "Task 2".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
+                task("task-1", "Task 1", true, vec![]),
+                task("task-2", "Task 2", false, vec![]),
             ],
+            ..Default::default()
         };
 
         let task = tasks_file.get_task_by_id("task-1");
@@ -558,31 +915,12 @@ tasks:
     fn test_get_ready_tasks() {
         let tasks_file = TasksFile {
             tasks: vec![
-                Task {
-                    id: "task-1".to_string(),
-                    title: "Task 1".to_string(),
-                    completed: true,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-2".to_string(),
-                    title: "Task 2".to_string(),
-                    completed: false,
-                    depends: vec!["task-1".to_string()],
-                },
-                Task {
-                    id: "task-3".to_string(),
-                    title: "Task 3".to_string(),
-                    completed: false,
-                    depends: vec!["task-2".to_string()],
-                },
-                Task {
-                    id: "task-4".to_string(),
-                    title: "Task 4".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
+                task("task-1", "Task 1", true, vec![]),
+                task("task-2", "Task 2", false, vec!["task-1"]),
+                task("task-3", "Task 3", false, vec!["task-2"]),
+                task("task-4", "Task 4", false, vec![]),
             ],
+            ..Default::default()
         };
 
         let ready = tasks_file.get_ready_tasks();
@@ -592,29 +930,61 @@ tasks:
         assert!(!ready.iter().any(|t| t.id == "task-3"));
     }
 
+    #[test]
+    fn test_get_ready_tasks_sorted_by_descending_urgency() {
+        let mut low = task("task-low", "Low priority", false, vec![]);
+        low.priority = Priority::Low;
+        let mut high = task("task-high", "High priority", false, vec![]);
+        high.priority = Priority::High;
+
+        let tasks_file = TasksFile {
+            tasks: vec![low, high],
+            ..Default::default()
+        };
+
+        let ready = tasks_file.get_ready_tasks();
+        assert_eq!(ready[0].id, "task-high");
+        assert_eq!(ready[1].id, "task-low");
+    }
+
+    #[test]
+    fn test_urgency_priority_ordering() {
+        let tasks_file = TasksFile { tasks: vec![], ..Default::default() };
+        let mut low = task("t", "t", false, vec![]);
+        let mut medium = task("t", "t", false, vec![]);
+        let mut high = task("t", "t", false, vec![]);
+        low.priority = Priority::Low;
+        medium.priority = Priority::Medium;
+        high.priority = Priority::High;
+
+        assert!(high.urgency(&tasks_file) > medium.urgency(&tasks_file));
+        assert!(medium.urgency(&tasks_file) > low.urgency(&tasks_file));
+    }
+
+    #[test]
+    fn test_urgency_blocking_boost() {
+        let blocker = task("blocker", "Blocks two tasks", false, vec![]);
+        let dependent_a = task("dep-a", "Dep A", false, vec!["blocker"]);
+        let dependent_b = task("dep-b", "Dep B", false, vec!["blocker"]);
+        let standalone = task("standalone", "No dependents", false, vec![]);
+
+        let tasks_file = TasksFile {
+            tasks: vec![blocker.clone(), dependent_a, dependent_b, standalone.clone()],
+            ..Default::default()
+        };
+
+        assert!(blocker.urgency(&tasks_file) > standalone.urgency(&tasks_file));
+    }
+
     #[test]
     fn test_get_blocked_tasks() {
         let tasks_file = TasksFile {
             tasks: vec![
-                Task {
-                    id: "task-1".to_string(),
-                    title: "Task 1".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-2".to_string(),
-                    title: "Task 2".to_string(),
-                    completed: false,
-                    depends: vec!["task-1".to_string()],
-                },
-                Task {
-                    id: "task-3".to_string(),
-                    title: "Task 3".to_string(),
-                    completed: false,
-                    depends: vec!["task-1".to_string(), "task-2".to_string()],
-                },
+                task("task-1", "Task 1", false, vec![]),
+                task("task-2", "Task 2", false, vec!["task-1"]),
+                task("task-3", "Task 3", false, vec!["task-1", "task-2"]),
             ],
+            ..Default::default()
         };
 
         let blocked = tasks_file.get_blocked_tasks();
@@ -632,25 +1002,11 @@ tasks:
     fn test_topological_order() {
         let tasks_file = TasksFile {
             tasks: vec![
-                Task {
-                    id: "task-1".to_string(),
-                    title: "Task 1".to_string(),
-                    completed: false,
-                    depends: vec![],
-                },
-                Task {
-                    id: "task-2".to_string(),
-                    title: "Task 2".to_string(),
-                    completed: false,
-                    depends: vec!["task-1".to_string()],
-                },
-                Task {
-                    id: "task-3".to_string(),
-                    title: "Task 3".to_string(),
-                    completed: false,
-                    depends: vec!["task-1".to_string(), "task-2".to_string()],
-                },
+                task("task-1", "Task 1", false, vec![]),
+                task("task-2", "Task 2", false, vec!["task-1"]),
+                task("task-3", "Task 3", false, vec!["task-1", "task-2"]),
             ],
+            ..Default::default()
         };
 
         let result = tasks_file.topological_order();
@@ -670,19 +1026,10 @@ tasks:
     fn test_topological_order_circular() {
         let tasks_file = TasksFile {
             tasks: vec![
-                Task {
-                    id: "task-1".to_string(),
-                    title: "Task 1".to_string(),
-                    completed: false,
-                    depends: vec!["task-2".to_string()],
-                },
-                Task {
-                    id: "task-2".to_string(),
-                    title: "Task 2".to_string(),
-                    completed: false,
-                    depends: vec!["task-1".to_string()],
-                },
+                task("task-1", "Task 1", false, vec!["task-2"]),
+                task("task-2", "Task 2", false, vec!["task-1"]),
             ],
+            ..Default::default()
         };
 
         let result = tasks_file.topological_order();
@@ -692,4 +1039,409 @@ tasks:
             .to_string()
             .contains("Circular dependency"));
     }
+
+    #[test]
+    fn test_topological_order_unknown_dependency() {
+        let tasks_file = TasksFile {
+            tasks: vec![task("task-1", "Task 1", false, vec!["missing"])],
+            ..Default::default()
+        };
+
+        let result = tasks_file.topological_order();
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("depends on non-existent task 'missing'"));
+    }
+
+    #[test]
+    fn test_critical_path_picks_longest_chain() {
+        let tasks_file = TasksFile {
+            tasks: vec![
+                task("task-1", "Task 1", false, vec![]),
+                task("task-2", "Task 2", false, vec!["task-1"]),
+                task("task-3", "Task 3", false, vec!["task-2"]),
+                task("shortcut", "Shortcut", false, vec!["task-1"]),
+            ],
+            ..Default::default()
+        };
+
+        let path = tasks_file.critical_path().unwrap();
+        let ids: Vec<&str> = path.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["task-1", "task-2", "task-3"]);
+    }
+
+    #[test]
+    fn test_critical_path_weighs_by_effort() {
+        let mut light = task("light", "Light", false, vec![]);
+        light.weight = 1;
+        let mut heavy = task("heavy", "Heavy", false, vec!["light"]);
+        heavy.weight = 10;
+        // Longer by task count (3 vs 2), but lighter by effort (3 vs 11).
+        let short_a = task("short-a", "Short A", false, vec!["light"]);
+        let short_b = task("short-b", "Short B", false, vec!["short-a"]);
+
+        let tasks_file = TasksFile {
+            tasks: vec![light, heavy, short_a, short_b],
+            ..Default::default()
+        };
+
+        let path = tasks_file.critical_path().unwrap();
+        let ids: Vec<&str> = path.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["light", "heavy"]);
+    }
+
+    #[test]
+    fn test_critical_path_errors_on_cycle() {
+        let tasks_file = TasksFile {
+            tasks: vec![
+                task("task-1", "Task 1", false, vec!["task-2"]),
+                task("task-2", "Task 2", false, vec!["task-1"]),
+            ],
+            ..Default::default()
+        };
+
+        assert!(tasks_file.critical_path().is_err());
+    }
+
+    #[test]
+    fn test_execution_waves_groups_independent_tasks() {
+        let tasks_file = TasksFile {
+            tasks: vec![
+                task("task-1", "Task 1", false, vec![]),
+                task("task-2", "Task 2", false, vec![]),
+                task("task-3", "Task 3", false, vec!["task-1", "task-2"]),
+            ],
+            ..Default::default()
+        };
+
+        let waves = tasks_file.execution_waves().unwrap();
+        assert_eq!(waves.len(), 2);
+        let wave_0_ids: Vec<&str> = waves[0].iter().map(|t| t.id.as_str()).collect();
+        assert!(wave_0_ids.contains(&"task-1"));
+        assert!(wave_0_ids.contains(&"task-2"));
+        assert_eq!(waves[1].len(), 1);
+        assert_eq!(waves[1][0].id, "task-3");
+    }
+
+    #[test]
+    fn test_execution_waves_excludes_completed_tasks() {
+        let tasks_file = TasksFile {
+            tasks: vec![
+                task("task-1", "Task 1", true, vec![]),
+                task("task-2", "Task 2", false, vec!["task-1"]),
+            ],
+            ..Default::default()
+        };
+
+        let waves = tasks_file.execution_waves().unwrap();
+        assert_eq!(waves.len(), 1);
+        assert_eq!(waves[0].len(), 1);
+        assert_eq!(waves[0][0].id, "task-2");
+    }
+
+    #[test]
+    fn test_execution_waves_empty_when_all_completed() {
+        let tasks_file = TasksFile {
+            tasks: vec![task("task-1", "Task 1", true, vec![])],
+            ..Default::default()
+        };
+
+        let waves = tasks_file.execution_waves().unwrap();
+        assert!(waves.is_empty());
+    }
+
+    #[test]
+    fn test_execution_waves_errors_on_cycle() {
+        let tasks_file = TasksFile {
+            tasks: vec![
+                task("task-1", "Task 1", false, vec!["task-2"]),
+                task("task-2", "Task 2", false, vec!["task-1"]),
+            ],
+            ..Default::default()
+        };
+
+        assert!(tasks_file.execution_waves().is_err());
+    }
+
+    #[test]
+    fn test_expand_templates_substitutes_file_vars_in_title() {
+        let mut tasks_file = TasksFile {
+            vars: HashMap::from([("env".to_string(), "staging".to_string())]),
+            tasks: vec![task("deploy", "Deploy to {{env}}", false, vec![])],
+        };
+
+        tasks_file.expand_templates().unwrap();
+        assert_eq!(tasks_file.tasks[0].title, "Deploy to staging");
+    }
+
+    #[test]
+    fn test_expand_templates_task_args_override_file_vars() {
+        let mut deploy = task("deploy", "Deploy to {{env}}", false, vec![]);
+        deploy.args = HashMap::from([("env".to_string(), "production".to_string())]);
+
+        let mut tasks_file = TasksFile {
+            vars: HashMap::from([("env".to_string(), "staging".to_string())]),
+            tasks: vec![deploy],
+        };
+
+        tasks_file.expand_templates().unwrap();
+        assert_eq!(tasks_file.tasks[0].title, "Deploy to production");
+    }
+
+    #[test]
+    fn test_expand_templates_substitutes_dependency_ids() {
+        let mut tasks_file = TasksFile {
+            vars: HashMap::from([("stage".to_string(), "build".to_string())]),
+            tasks: vec![
+                task("build", "Build", false, vec![]),
+                task("test", "Test", false, vec!["{{stage}}"]),
+            ],
+        };
+
+        tasks_file.expand_templates().unwrap();
+        assert_eq!(tasks_file.tasks[1].depends, vec!["build".to_string()]);
+    }
+
+    #[test]
+    fn test_expand_templates_errors_on_undefined_variable() {
+        let mut tasks_file = TasksFile {
+            tasks: vec![task("deploy", "Deploy to {{env}}", false, vec![])],
+            ..Default::default()
+        };
+
+        let err = tasks_file.expand_templates().unwrap_err();
+        assert!(err.to_string().contains("unresolved template variable"));
+    }
+
+    #[test]
+    fn test_load_from_file_expands_templates_before_validation() {
+        let temp_dir = std::env::temp_dir();
+        let tasks_path = temp_dir.join("templated_tasks.yaml");
+        let mut file = fs::File::create(&tasks_path).unwrap();
+        writeln!(
+            file,
+            r#"
+vars:
+  env: "staging"
+tasks:
+  - id: "deploy"
+    title: "Deploy to {{{{env}}}}"
+    completed: false
+    depends: []
+"#
+        )
+        .unwrap();
+
+        let tasks_file = TasksFile::load_from(&tasks_path).unwrap();
+        assert_eq!(tasks_file.tasks[0].title, "Deploy to staging");
+
+        fs::remove_file(&tasks_path).unwrap();
+    }
+
+    #[test]
+    fn test_overdue_tasks_excludes_completed_and_future() {
+        let today = NaiveDate::from_ymd_opt(2026, 7, 29).unwrap();
+        let mut overdue = task("overdue", "Overdue", false, vec![]);
+        overdue.due = Some(NaiveDate::from_ymd_opt(2026, 7, 1).unwrap());
+        let mut future = task("future", "Future", false, vec![]);
+        future.due = Some(NaiveDate::from_ymd_opt(2026, 8, 1).unwrap());
+        let mut completed_overdue = task("completed", "Completed overdue", true, vec![]);
+        completed_overdue.due = Some(NaiveDate::from_ymd_opt(2026, 7, 1).unwrap());
+
+        let tasks_file = TasksFile {
+            tasks: vec![overdue, future, completed_overdue],
+            ..Default::default()
+        };
+
+        let result = tasks_file.overdue_tasks(today);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, "overdue");
+    }
+
+    #[test]
+    fn test_total_logged_and_estimated() {
+        let mut t1 = task("t1", "T1", false, vec![]);
+        t1.estimate = Some(Duration::hours(2));
+        t1.time_entries = vec![TimeEntry {
+            date: NaiveDate::from_ymd_opt(2026, 7, 1).unwrap(),
+            message: None,
+            minutes: 90,
+        }];
+        let mut t2 = task("t2", "T2", false, vec![]);
+        t2.estimate = Some(Duration::hours(1));
+        t2.time_entries = vec![TimeEntry {
+            date: NaiveDate::from_ymd_opt(2026, 7, 2).unwrap(),
+            message: Some("wrote tests".to_string()),
+            minutes: 30,
+        }];
+
+        let tasks_file = TasksFile {
+            tasks: vec![t1, t2],
+            ..Default::default()
+        };
+
+        assert_eq!(tasks_file.total_logged(), Duration::minutes(120));
+        assert_eq!(tasks_file.total_estimated(), Duration::hours(3));
+    }
+
+    #[test]
+    fn test_tasks_by_due_sorts_with_none_last() {
+        let mut no_due = task("no-due", "No due", false, vec![]);
+        no_due.due = None;
+        let mut later = task("later", "Later", false, vec![]);
+        later.due = Some(NaiveDate::from_ymd_opt(2026, 8, 15).unwrap());
+        let mut sooner = task("sooner", "Sooner", false, vec![]);
+        sooner.due = Some(NaiveDate::from_ymd_opt(2026, 8, 1).unwrap());
+
+        let tasks_file = TasksFile {
+            tasks: vec![no_due, later, sooner],
+            ..Default::default()
+        };
+
+        let ids: Vec<&str> = tasks_file.tasks_by_due().iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["sooner", "later", "no-due"]);
+    }
+
+    #[test]
+    fn test_urgency_boosted_by_imminent_due_date() {
+        let tasks_file = TasksFile { tasks: vec![], ..Default::default() };
+
+        let mut soon = task("soon", "Due today", false, vec![]);
+        soon.due = Some(chrono::Utc::now().date_naive());
+        let mut far = task("far", "Due far away", false, vec![]);
+        far.due = Some(chrono::Utc::now().date_naive() + Duration::days(365));
+
+        assert!(soon.urgency(&tasks_file) > far.urgency(&tasks_file));
+    }
+
+    #[test]
+    fn test_recompute_then_stale_tasks_is_clean() {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "lazyagent_fingerprint_test_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let input_path = temp_dir.join("input.txt");
+        fs::write(&input_path, "hello").unwrap();
+
+        let mut source_task = task("source", "Source", true, vec![]);
+        source_task.inputs = vec![input_path.to_string_lossy().to_string()];
+
+        let mut tasks_file = TasksFile {
+            tasks: vec![source_task],
+            ..Default::default()
+        };
+
+        tasks_file.recompute_fingerprints();
+        assert!(tasks_file.tasks[0].fingerprint.is_some());
+        assert!(tasks_file.stale_tasks().unwrap().is_empty());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_stale_tasks_flags_changed_input() {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "lazyagent_fingerprint_stale_test_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let input_path = temp_dir.join("input.txt");
+        fs::write(&input_path, "hello").unwrap();
+
+        let mut source_task = task("source", "Source", true, vec![]);
+        source_task.inputs = vec![input_path.to_string_lossy().to_string()];
+
+        let mut tasks_file = TasksFile {
+            tasks: vec![source_task],
+            ..Default::default()
+        };
+        tasks_file.recompute_fingerprints();
+
+        fs::write(&input_path, "hello, world").unwrap();
+
+        let stale = tasks_file.stale_tasks().unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].id, "source");
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_stale_tasks_propagates_downstream() {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "lazyagent_fingerprint_downstream_test_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&temp_dir).unwrap();
+        let input_path = temp_dir.join("input.txt");
+        fs::write(&input_path, "hello").unwrap();
+
+        let mut upstream = task("upstream", "Upstream", true, vec![]);
+        upstream.inputs = vec![input_path.to_string_lossy().to_string()];
+        let downstream = task("downstream", "Downstream", true, vec!["upstream"]);
+
+        let mut tasks_file = TasksFile {
+            tasks: vec![upstream, downstream],
+            ..Default::default()
+        };
+        tasks_file.recompute_fingerprints();
+        assert!(tasks_file.stale_tasks().unwrap().is_empty());
+
+        fs::write(&input_path, "changed").unwrap();
+
+        let stale_ids: Vec<&str> = tasks_file
+            .stale_tasks()
+            .unwrap()
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert!(stale_ids.contains(&"upstream"));
+        assert!(stale_ids.contains(&"downstream"));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_stale_tasks_ignores_tasks_without_fingerprint() {
+        let tasks_file = TasksFile {
+            tasks: vec![task("done", "Done, never fingerprinted", true, vec![])],
+            ..Default::default()
+        };
+
+        assert!(tasks_file.stale_tasks().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_check_no_cycles_reports_exact_chain() {
+        let tasks_file = TasksFile {
+            tasks: vec![
+                task("task-1", "Task 1", false, vec!["task-2"]),
+                task("task-2", "Task 2", false, vec!["task-3"]),
+                task("task-3", "Task 3", false, vec!["task-1"]),
+            ],
+            ..Default::default()
+        };
+
+        let err = tasks_file.validate().unwrap_err().to_string();
+        assert!(err.contains("Circular dependency detected"));
+        assert!(err.contains("task-1 -> task-2 -> task-3 -> task-1"));
+    }
+
+    #[test]
+    fn test_check_no_cycles_ignores_unreachable_acyclic_tasks() {
+        let tasks_file = TasksFile {
+            tasks: vec![
+                task("standalone", "Standalone", false, vec![]),
+                task("task-1", "Task 1", false, vec!["task-2"]),
+                task("task-2", "Task 2", false, vec!["task-1"]),
+            ],
+            ..Default::default()
+        };
+
+        let err = tasks_file.validate().unwrap_err().to_string();
+        assert!(err.contains("task-1 -> task-2 -> task-1"));
+    }
 }